@@ -0,0 +1,135 @@
+use std::fmt;
+
+/// The `xid`/Crockford-style base32-hex alphabet: 5 bits per character, ordered
+/// so that lexicographic string order matches numeric order.
+const ALPHABET: &[u8; 32] = b"0123456789abcdefghijklmnopqrstuv";
+
+/// Width, in base32 characters, of an encoded `u64` (`ceil(64 / 5)`).
+const ENCODED_LEN: usize = 13;
+
+/// Errors returned by [`decode`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseIdError {
+    /// The string contained a character outside the `0-9a-v` alphabet.
+    InvalidChar(char),
+    /// The string had more than 13 characters.
+    TooLong { len: usize },
+    /// The string decodes to a value wider than 64 bits.
+    Overflow,
+}
+
+impl fmt::Display for ParseIdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseIdError::InvalidChar(c) => write!(f, "invalid base32 character '{c}'"),
+            ParseIdError::TooLong { len } => {
+                write!(f, "encoded id too long: {len} characters, expected at most {ENCODED_LEN}")
+            }
+            ParseIdError::Overflow => write!(f, "encoded id does not fit in a u64"),
+        }
+    }
+}
+
+impl std::error::Error for ParseIdError {}
+
+/// Encodes `id` as a lexicographically-sortable, 13-character base32-hex string.
+///
+/// The value is rendered big-endian, most-significant group first, so that
+/// comparing the encoded strings byte-by-byte gives the same order as
+/// comparing the original integers.
+///
+/// # Examples
+///
+/// ```
+/// use idable::encode;
+///
+/// assert_eq!(encode(0), "0000000000000");
+/// assert!(encode(1) < encode(2));
+/// ```
+pub fn encode(id: u64) -> String {
+    let mut s = String::with_capacity(ENCODED_LEN);
+    for i in 0..ENCODED_LEN {
+        let shift = (ENCODED_LEN - 1 - i) * 5;
+        let index = ((id >> shift) & 0x1f) as usize;
+        s.push(ALPHABET[index] as char);
+    }
+    s
+}
+
+fn decode_char(c: char) -> Result<u8, ParseIdError> {
+    match c {
+        '0'..='9' => Ok(c as u8 - b'0'),
+        'a'..='v' => Ok(c as u8 - b'a' + 10),
+        _ => Err(ParseIdError::InvalidChar(c)),
+    }
+}
+
+/// Decodes a string produced by [`encode`] back into a `u64`.
+///
+/// Rejects characters outside the `0-9a-v` alphabet, strings longer than
+/// 13 characters, and strings that decode to a value wider than 64 bits.
+///
+/// # Examples
+///
+/// ```
+/// use idable::{decode, encode};
+///
+/// let id = 123456789;
+/// assert_eq!(decode(&encode(id)).unwrap(), id);
+/// ```
+pub fn decode(s: &str) -> Result<u64, ParseIdError> {
+    if s.len() > ENCODED_LEN {
+        return Err(ParseIdError::TooLong { len: s.len() });
+    }
+    let mut value: u64 = 0;
+    for c in s.chars() {
+        let digit = decode_char(c)?;
+        if value >> 59 != 0 {
+            return Err(ParseIdError::Overflow);
+        }
+        value = (value << 5) | digit as u64;
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() {
+        for id in [0, 1, 42, u64::MAX, u64::MAX / 2, 1637806706000] {
+            let encoded = encode(id);
+            assert_eq!(encoded.len(), ENCODED_LEN);
+            assert_eq!(decode(&encoded).unwrap(), id);
+        }
+    }
+
+    #[test]
+    fn test_encoding_preserves_order() {
+        let mut ids = [5u64, 1, u64::MAX, 0, 1000, 999];
+        let mut encoded: Vec<String> = ids.iter().map(|&id| encode(id)).collect();
+        ids.sort_unstable();
+        encoded.sort();
+        let decoded: Vec<u64> = encoded.iter().map(|s| decode(s).unwrap()).collect();
+        assert_eq!(decoded, ids);
+    }
+
+    #[test]
+    fn test_decode_rejects_invalid_char() {
+        assert_eq!(decode("000000000000w"), Err(ParseIdError::InvalidChar('w')));
+    }
+
+    #[test]
+    fn test_decode_rejects_overlong_strings() {
+        assert_eq!(
+            decode("00000000000000"),
+            Err(ParseIdError::TooLong { len: 14 })
+        );
+    }
+
+    #[test]
+    fn test_decode_rejects_overflow() {
+        assert_eq!(decode("vvvvvvvvvvvvv"), Err(ParseIdError::Overflow));
+    }
+}
@@ -0,0 +1,66 @@
+use std::sync::atomic::{AtomicU64, Ordering::Acquire, Ordering::Release};
+
+use crate::timestamp_seq::{Clock, SystemClock};
+use crate::TimestampSeq;
+
+/// A [`TimestampSeq`] wrapper that guarantees strictly increasing output.
+///
+/// See [`TimestampSeq::monotonic`] for how to construct one, and the
+/// trade-off it makes against the plain, non-monotonic fast path.
+pub struct MonotonicTimestampSeq<C: Clock = SystemClock> {
+    inner: TimestampSeq<C>,
+    last_emitted: AtomicU64,
+}
+
+impl<C: Clock> MonotonicTimestampSeq<C> {
+    pub(crate) fn new(inner: TimestampSeq<C>) -> Self {
+        MonotonicTimestampSeq {
+            inner,
+            last_emitted: AtomicU64::new(0),
+        }
+    }
+
+    /// Generates the next id, guaranteed to be strictly greater than every
+    /// id this generator has previously returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use idable::TimestampSeq;
+    ///
+    /// let mut seq = TimestampSeq::new().monotonic();
+    /// let id1 = seq.next_id();
+    /// let id2 = seq.next_id();
+    /// assert!(id2 > id1);
+    /// ```
+    pub fn next_id(&mut self) -> u64 {
+        let id = self.inner.next_id();
+        let last = self.last_emitted.load(Acquire);
+        let id = if id <= last {
+            self.inner.bump_timestamp(last)
+        } else {
+            id
+        };
+        self.last_emitted.store(id, Release);
+        id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TimestampSeqBuilder;
+
+    #[test]
+    fn test_monotonic_next_id_always_increases() {
+        let config = TimestampSeqBuilder::new(0).sequence_bits(1).build().unwrap();
+        let mut seq = TimestampSeq::with_config(config).monotonic();
+
+        let mut last = seq.next_id();
+        for _ in 0..100 {
+            let id = seq.next_id();
+            assert!(id > last, "{id} did not increase past {last}");
+            last = id;
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use std::sync::atomic::{AtomicU64, Ordering::Release, Ordering::SeqCst};
+
+pub type NID = u64;
+pub type SIDGEN = AtomicU64;
+pub type SID = u64;
+
+/// Represents a sequential number generator.
+#[derive(Default)]
+pub struct Seq(SIDGEN);
+
+impl Seq {
+    /// Creates a new `Seq` instance with an initial value of 1.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use idable::Seq;
+    ///
+    /// let mut seq = Seq::new();
+    /// ```
+    pub fn new() -> Seq {
+        Seq(SIDGEN::new(0))
+    }
+
+    /// Generates the next sequential ID.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use idable::Seq;
+    ///
+    /// let mut seq = Seq::new();
+    /// let next_id = seq.next_id();
+    /// ```
+    pub fn next_id(&mut self) -> SID {
+        self.0.fetch_add(1, SeqCst)
+    }
+
+    /// Resets the sequential number to its initial value of 1.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use idable::Seq;
+    ///
+    /// let mut seq = Seq::new();
+    /// seq.reset();
+    /// ```
+    pub fn reset(&mut self) {
+        self.0.store(0, Release);
+    }
+}
+impl From<SID> for Seq {
+    fn from(value: SID) -> Self {
+        Seq(SIDGEN::new(value))
+    }
+}
@@ -0,0 +1,31 @@
+//! Test-only helpers shared across this crate's generators.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+
+use crate::Clock;
+
+/// A scripted [`Clock`] for deterministic tests: returns each timestamp in
+/// order, then keeps returning the last one forever (a "frozen" clock).
+pub(crate) struct MockClock {
+    timestamps: RefCell<VecDeque<u64>>,
+}
+
+impl MockClock {
+    pub(crate) fn new(timestamps: impl IntoIterator<Item = u64>) -> Self {
+        MockClock {
+            timestamps: RefCell::new(timestamps.into_iter().collect()),
+        }
+    }
+}
+
+impl Clock for MockClock {
+    fn now_millis(&self) -> u64 {
+        let mut timestamps = self.timestamps.borrow_mut();
+        if timestamps.len() > 1 {
+            timestamps.pop_front().unwrap()
+        } else {
+            *timestamps.front().expect("MockClock ran out of timestamps")
+        }
+    }
+}
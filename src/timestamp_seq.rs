@@ -0,0 +1,547 @@
+use std::{
+    fmt,
+    sync::atomic::{
+        AtomicU64,
+        Ordering::{Acquire, Release, SeqCst},
+    },
+    time::SystemTime,
+};
+
+use crate::MonotonicTimestampSeq;
+
+pub const EPOCH: u64 = 1637806706000;
+
+/// Errors that can occur while building a [`TimestampSeqConfig`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigError {
+    /// `timestamp_bits + machine_id_bits + sequence_bits + clock_seq_bits` exceeded 63.
+    BitsTooWide { total: u16 },
+    /// The supplied `machine_id` does not fit in `machine_id_bits`.
+    MachineIdOutOfRange { machine_id: u64, max: u64 },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::BitsTooWide { total } => {
+                write!(f, "timestamp_bits + machine_id_bits + sequence_bits + clock_seq_bits must be <= 63, got {total}")
+            }
+            ConfigError::MachineIdOutOfRange { machine_id, max } => {
+                write!(f, "machine_id {machine_id} does not fit in machine_id_bits (max {max})")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// The bit layout and machine identity used to compose IDs in a [`TimestampSeq`].
+///
+/// From the least to the most significant bit: `sequence`, `clock_seq`,
+/// `machine_id`, `timestamp`.
+#[derive(Debug, Clone, Copy)]
+pub struct TimestampSeqConfig {
+    machine_id: u64,
+    sequence_mask: u64,
+    clock_seq_mask: u64,
+    machine_id_mask: u64,
+    clock_seq_shift: u8,
+    machine_shift: u8,
+    timestamp_shift: u8,
+}
+
+impl Default for TimestampSeqConfig {
+    fn default() -> Self {
+        TimestampSeqBuilder::new(0)
+            .build()
+            .expect("default bit layout is always valid")
+    }
+}
+
+/// Builds a [`TimestampSeqConfig`], validating the bit layout before use.
+///
+/// Defaults to the classic Snowflake split: 39 timestamp bits, 10 machine-id
+/// bits, 12 sequence bits and 2 clock-sequence bits.
+pub struct TimestampSeqBuilder {
+    machine_id: u64,
+    timestamp_bits: u8,
+    machine_id_bits: u8,
+    sequence_bits: u8,
+    clock_seq_bits: u8,
+}
+
+impl TimestampSeqBuilder {
+    /// Creates a builder for the given `machine_id`, using the default bit layout.
+    pub fn new(machine_id: u64) -> Self {
+        TimestampSeqBuilder {
+            machine_id,
+            timestamp_bits: 39,
+            machine_id_bits: 10,
+            sequence_bits: 12,
+            clock_seq_bits: 2,
+        }
+    }
+
+    /// Sets the number of bits reserved for the timestamp.
+    pub fn timestamp_bits(mut self, bits: u8) -> Self {
+        self.timestamp_bits = bits;
+        self
+    }
+
+    /// Sets the number of bits reserved for the machine id.
+    pub fn machine_id_bits(mut self, bits: u8) -> Self {
+        self.machine_id_bits = bits;
+        self
+    }
+
+    /// Sets the number of bits reserved for the per-millisecond sequence.
+    pub fn sequence_bits(mut self, bits: u8) -> Self {
+        self.sequence_bits = bits;
+        self
+    }
+
+    /// Sets the number of bits reserved for the clock sequence, bumped whenever
+    /// the clock is observed to move backwards (see [`TimestampSeq::next_id`]).
+    pub fn clock_seq_bits(mut self, bits: u8) -> Self {
+        self.clock_seq_bits = bits;
+        self
+    }
+
+    /// Validates the layout and produces a [`TimestampSeqConfig`].
+    pub fn build(self) -> Result<TimestampSeqConfig, ConfigError> {
+        let total = self.timestamp_bits as u16
+            + self.machine_id_bits as u16
+            + self.sequence_bits as u16
+            + self.clock_seq_bits as u16;
+        if total > 63 {
+            return Err(ConfigError::BitsTooWide { total });
+        }
+        let machine_id_mask = (1u64 << self.machine_id_bits) - 1;
+        if self.machine_id > machine_id_mask {
+            return Err(ConfigError::MachineIdOutOfRange {
+                machine_id: self.machine_id,
+                max: machine_id_mask,
+            });
+        }
+        let sequence_mask = (1u64 << self.sequence_bits) - 1;
+        let clock_seq_mask = (1u64 << self.clock_seq_bits) - 1;
+        let clock_seq_shift = self.sequence_bits;
+        let machine_shift = self.sequence_bits + self.clock_seq_bits;
+        Ok(TimestampSeqConfig {
+            machine_id: self.machine_id,
+            sequence_mask,
+            clock_seq_mask,
+            machine_id_mask,
+            clock_seq_shift,
+            machine_shift,
+            timestamp_shift: machine_shift + self.machine_id_bits,
+        })
+    }
+}
+
+/// A source of the current time, in milliseconds since the Unix epoch.
+///
+/// Borrowed from the `ClockSequence`/`TimeClockSequence` pattern used by the
+/// `uuid` crate's v1 generators: swapping the clock out lets [`TimestampSeq`]
+/// be driven deterministically in tests instead of always hitting the system
+/// clock.
+pub trait Clock {
+    /// Returns the current time in milliseconds since [`EPOCH`]'s reference point
+    /// (the Unix epoch).
+    fn now_millis(&self) -> u64;
+}
+
+/// The default [`Clock`], backed by [`SystemTime::now`].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_millis(&self) -> u64 {
+        SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect("Clock moved backwards!")
+            .as_millis() as u64
+    }
+}
+
+/// Errors returned by [`TimestampSeq::try_next_id`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClockError {
+    /// The clock moved backwards past the timestamp recorded when this
+    /// generator's sequence last wrapped to `0`.
+    ClockMovedBackwards { observed: u64, last: u64 },
+}
+
+impl fmt::Display for ClockError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClockError::ClockMovedBackwards { observed, last } => write!(
+                f,
+                "clock moved backwards: observed {observed}, last seen {last}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ClockError {}
+
+/// Represents a timestamped sequence generator, generic over its [`Clock`] source.
+#[derive(Default)]
+pub struct TimestampSeq<C: Clock = SystemClock> {
+    sequence: AtomicU64,
+    last_timestamp: AtomicU64,
+    clock_seq: AtomicU64,
+    config: TimestampSeqConfig,
+    clock: C,
+}
+
+impl TimestampSeq<SystemClock> {
+    /// Creates a new `TimestampSeq` instance with the default bit layout and machine id `0`.
+    pub fn new() -> TimestampSeq<SystemClock> {
+        TimestampSeq::default()
+    }
+
+    /// Starts building a `TimestampSeq` for the given `machine_id`.
+    pub fn builder(machine_id: u64) -> TimestampSeqBuilder {
+        TimestampSeqBuilder::new(machine_id)
+    }
+
+    /// Creates a `TimestampSeq` from an already validated [`TimestampSeqConfig`],
+    /// using the system clock.
+    pub fn with_config(config: TimestampSeqConfig) -> TimestampSeq<SystemClock> {
+        TimestampSeq::with_clock(config, SystemClock)
+    }
+}
+
+impl<C: Clock> TimestampSeq<C> {
+    /// Creates a `TimestampSeq` from an already validated [`TimestampSeqConfig`]
+    /// and a custom [`Clock`] source.
+    pub fn with_clock(config: TimestampSeqConfig, clock: C) -> TimestampSeq<C> {
+        TimestampSeq {
+            sequence: AtomicU64::new(0),
+            last_timestamp: AtomicU64::new(0),
+            clock_seq: AtomicU64::new(0),
+            config,
+            clock,
+        }
+    }
+
+    fn wait_next_millis(&self) {
+        let last_timestamp = self.last_timestamp.load(Acquire);
+        while self.clock.now_millis() <= last_timestamp {}
+    }
+
+    /// Reads the timestamp to use for the next id, given the `sequence` number
+    /// that was just claimed for this call.
+    ///
+    /// Returns `Err` if the clock has moved backwards past the timestamp
+    /// recorded when the sequence last wrapped to `0`, leaving the rollover decision to
+    /// the caller (see [`next_id`](Self::next_id) vs [`try_next_id`](Self::try_next_id)).
+    fn read_timestamp(&self, sequence: u64) -> Result<u64, ClockError> {
+        let mut new_timestamp = self.clock.now_millis();
+        let last_timestamp = self.last_timestamp.load(Acquire);
+        if new_timestamp < last_timestamp {
+            return Err(ClockError::ClockMovedBackwards {
+                observed: new_timestamp,
+                last: last_timestamp,
+            });
+        }
+        // If the sequence goes one cycle, check if the timestamp hasn't changed yet
+        if sequence == 0 {
+            if last_timestamp == new_timestamp {
+                self.wait_next_millis();
+                new_timestamp = self.clock.now_millis();
+            }
+            self.last_timestamp.fetch_max(new_timestamp, Release);
+        }
+        Ok(new_timestamp)
+    }
+
+    fn compose(&self, timestamp: u64, sequence: u64) -> u64 {
+        let clock_seq = self.clock_seq.load(Acquire) & self.config.clock_seq_mask;
+        (timestamp - EPOCH) << self.config.timestamp_shift
+            | self.config.machine_id << self.config.machine_shift
+            | clock_seq << self.config.clock_seq_shift
+            | sequence
+    }
+
+    /// Recomposes `id` with its timestamp field advanced by one tick and its
+    /// sequence reset to `0`, keeping the same machine id and clock sequence.
+    ///
+    /// Used by [`MonotonicTimestampSeq`](crate::MonotonicTimestampSeq) to push
+    /// past a timestamp/sequence pair that wouldn't otherwise be an increase.
+    pub(crate) fn bump_timestamp(&self, id: u64) -> u64 {
+        let (timestamp, machine_id, clock_seq, _sequence) = self.into_parts(id);
+        (timestamp + 1) << self.config.timestamp_shift
+            | machine_id << self.config.machine_shift
+            | clock_seq << self.config.clock_seq_shift
+    }
+
+    /// Wraps this generator so that [`MonotonicTimestampSeq::next_id`] is
+    /// guaranteed to return strictly increasing values.
+    ///
+    /// Prefer the plain, non-monotonic [`next_id`](Self::next_id) unless
+    /// callers rely on the output as a sort key or CRDT ordering token: the
+    /// monotonic wrapper pays for that guarantee by occasionally advancing
+    /// its timestamp ahead of the real clock (when the sequence wraps without
+    /// the millisecond advancing past the previous id's) instead
+    /// of waiting for the clock to catch up.
+    pub fn monotonic(self) -> MonotonicTimestampSeq<C> {
+        MonotonicTimestampSeq::new(self)
+    }
+
+    /// Generates the next unique ID based on the timestamp, machine id, clock
+    /// sequence and sequence number.
+    ///
+    /// The generated ID is a combination of timestamp, machine id and sequence number,
+    /// ensuring uniqueness across processes sharing the same machine id space.
+    /// * Note that it is not guaranteed to be in increasing order。
+    ///
+    /// If the clock is observed to move backwards (e.g. an NTP step), this
+    /// does not panic: instead it keeps using the last known-good timestamp
+    /// and bumps the clock sequence (wrapping within `clock_seq_bits`) so the
+    /// composed id stays unique. Use [`try_next_id`](Self::try_next_id) if you
+    /// would rather get an explicit error in that case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use idable::TimestampSeq;
+    ///
+    /// let mut timestamp_seq = TimestampSeq::new();
+    ///
+    /// // Generate the next unique ID.
+    /// let unique_id = timestamp_seq.next_id();
+    ///
+    /// // Print the generated unique ID.
+    /// println!("Generated Unique ID: {}", unique_id);
+    /// ```
+    pub fn next_id(&mut self) -> u64 {
+        let sequence = self.sequence.fetch_add(1, SeqCst) & self.config.sequence_mask;
+        match self.read_timestamp(sequence) {
+            Ok(timestamp) => self.compose(timestamp, sequence),
+            Err(ClockError::ClockMovedBackwards { last, .. }) => {
+                self.clock_seq.fetch_add(1, SeqCst);
+                self.compose(last, sequence)
+            }
+        }
+    }
+
+    /// Like [`next_id`](Self::next_id), but returns [`ClockError`] instead of
+    /// rolling the clock sequence over when the clock has moved backwards.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use idable::TimestampSeq;
+    ///
+    /// let mut timestamp_seq = TimestampSeq::new();
+    /// let unique_id = timestamp_seq.try_next_id().expect("system clock is monotonic here");
+    /// ```
+    pub fn try_next_id(&mut self) -> Result<u64, ClockError> {
+        let sequence = self.sequence.fetch_add(1, SeqCst) & self.config.sequence_mask;
+        let timestamp = self.read_timestamp(sequence)?;
+        Ok(self.compose(timestamp, sequence))
+    }
+
+    /// Splits a composed `id` back into its `(timestamp, machine_id, clock_seq, sequence)`
+    /// parts, using this generator's bit layout.
+    pub fn into_parts(&self, id: u64) -> (u64, u64, u64, u64) {
+        let sequence = id & self.config.sequence_mask;
+        let clock_seq = (id >> self.config.clock_seq_shift) & self.config.clock_seq_mask;
+        let machine_id = (id >> self.config.machine_shift) & self.config.machine_id_mask;
+        let timestamp = id >> self.config.timestamp_shift;
+        (timestamp, machine_id, clock_seq, sequence)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::MockClock;
+
+    // Helper function to wait for the next millisecond
+    fn wait_for_next_millis() {
+        std::thread::sleep(std::time::Duration::from_millis(1));
+    }
+
+    fn small_sequence_seq() -> TimestampSeq {
+        TimestampSeq::with_config(
+            TimestampSeq::builder(0)
+                .sequence_bits(1)
+                .build()
+                .unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_next_generates_unique_ids() {
+        let mut timestamp_seq = small_sequence_seq();
+
+        // Generate multiple unique IDs and ensure they are different.
+        let id1 = timestamp_seq.next_id();
+        let id2 = timestamp_seq.next_id();
+        let id3 = timestamp_seq.next_id();
+
+        assert_ne!(id1, id2);
+        assert_ne!(id2, id3);
+        assert_ne!(id1, id3);
+    }
+
+    #[test]
+    fn test_next_increases_sequence() {
+        let mut timestamp_seq = small_sequence_seq();
+
+        // Generate IDs and ensure the sequence increases.
+        let id1 = timestamp_seq.next_id();
+        let id2 = timestamp_seq.next_id();
+
+        assert!(id2 > id1);
+    }
+
+    #[test]
+    fn test_next_does_not_repeat_ids() {
+        let mut timestamp_seq = small_sequence_seq();
+
+        // Generate multiple IDs and ensure no repetition.
+        let id1 = timestamp_seq.next_id();
+        let id2 = timestamp_seq.next_id();
+        let id3 = timestamp_seq.next_id();
+        let id4 = timestamp_seq.next_id();
+
+        assert_ne!(id1, id2);
+        assert_ne!(id2, id3);
+        assert_ne!(id3, id4);
+        assert_ne!(id1, id4);
+        println!("{id1} {id2} {id3} {id4}");
+        println!(
+            "{:?} {:?} {:?} {:?}",
+            timestamp_seq.into_parts(id1),
+            timestamp_seq.into_parts(id2),
+            timestamp_seq.into_parts(id3),
+            timestamp_seq.into_parts(id4)
+        );
+    }
+
+    #[test]
+    fn test_next_wait_for_next_millis() {
+        let mut timestamp_seq = small_sequence_seq();
+
+        // Generate two IDs in quick succession and ensure the second one has a greater timestamp.
+        let id1 = timestamp_seq.next_id();
+        wait_for_next_millis();
+        let id2 = timestamp_seq.next_id();
+
+        let (timestamp1, _, _, _) = timestamp_seq.into_parts(id1);
+        let (timestamp2, _, _, _) = timestamp_seq.into_parts(id2);
+
+        assert!(timestamp2 > timestamp1);
+    }
+
+    #[test]
+    fn test_machine_id_is_encoded_in_id() {
+        let config = TimestampSeq::builder(7).build().unwrap();
+        let mut timestamp_seq = TimestampSeq::with_config(config);
+
+        let id = timestamp_seq.next_id();
+        let (_, machine_id, _, _) = timestamp_seq.into_parts(id);
+
+        assert_eq!(machine_id, 7);
+    }
+
+    #[test]
+    fn test_frozen_mock_clock_forces_wait_path() {
+        let config = TimestampSeqBuilder::new(0).sequence_bits(1).build().unwrap();
+        let clock = MockClock::new([EPOCH, EPOCH, EPOCH, EPOCH, EPOCH + 1]);
+        let mut timestamp_seq = TimestampSeq::with_clock(config, clock);
+
+        let id1 = timestamp_seq.next_id(); // sequence 0, clock frozen at EPOCH
+        let id2 = timestamp_seq.next_id(); // sequence 1, still frozen at EPOCH
+        let id3 = timestamp_seq.next_id(); // sequence wraps to 0, must wait for EPOCH + 1
+
+        let (ts1, _, _, seq1) = timestamp_seq.into_parts(id1);
+        let (ts2, _, _, seq2) = timestamp_seq.into_parts(id2);
+        let (ts3, _, _, seq3) = timestamp_seq.into_parts(id3);
+
+        assert_eq!((ts1, seq1), (0, 0));
+        assert_eq!((ts2, seq2), (0, 1));
+        assert_eq!((ts3, seq3), (1, 0));
+    }
+
+    #[test]
+    fn test_mock_clock_scripted_sequence_is_deterministic() {
+        let config = TimestampSeqBuilder::new(0).build().unwrap();
+        let clock = MockClock::new([EPOCH, EPOCH + 5, EPOCH + 10]);
+        let mut timestamp_seq = TimestampSeq::with_clock(config, clock);
+
+        let id1 = timestamp_seq.next_id();
+        let id2 = timestamp_seq.next_id();
+        let id3 = timestamp_seq.next_id();
+
+        let (ts1, _, _, _) = timestamp_seq.into_parts(id1);
+        let (ts2, _, _, _) = timestamp_seq.into_parts(id2);
+        let (ts3, _, _, _) = timestamp_seq.into_parts(id3);
+
+        assert_eq!((ts1, ts2, ts3), (0, 5, 10));
+    }
+
+    #[test]
+    fn test_next_id_rolls_clock_seq_over_on_backwards_clock() {
+        let config = TimestampSeqBuilder::new(0).clock_seq_bits(2).build().unwrap();
+        let clock = MockClock::new([EPOCH + 10, EPOCH]);
+        let mut timestamp_seq = TimestampSeq::with_clock(config, clock);
+
+        let id1 = timestamp_seq.next_id();
+        let id2 = timestamp_seq.next_id(); // clock stepped backwards to EPOCH
+
+        let (ts1, _, clock_seq1, _) = timestamp_seq.into_parts(id1);
+        let (ts2, _, clock_seq2, _) = timestamp_seq.into_parts(id2);
+
+        assert_eq!(ts1, 10);
+        assert_eq!(clock_seq1, 0);
+        // The id still uses the last known-good timestamp, disambiguated by clock_seq.
+        assert_eq!(ts2, 10);
+        assert_eq!(clock_seq2, 1);
+        assert_ne!(id1, id2);
+    }
+
+    #[test]
+    fn test_try_next_id_reports_backwards_clock() {
+        let config = TimestampSeqBuilder::new(0).build().unwrap();
+        let clock = MockClock::new([EPOCH + 10, EPOCH]);
+        let mut timestamp_seq = TimestampSeq::with_clock(config, clock);
+
+        timestamp_seq.try_next_id().unwrap();
+        let err = timestamp_seq.try_next_id().unwrap_err();
+
+        assert_eq!(
+            err,
+            ClockError::ClockMovedBackwards {
+                observed: EPOCH,
+                last: EPOCH + 10,
+            }
+        );
+    }
+
+    #[test]
+    fn test_builder_rejects_oversized_layout() {
+        let err = TimestampSeq::builder(0)
+            .timestamp_bits(41)
+            .machine_id_bits(12)
+            .sequence_bits(12)
+            .build()
+            .unwrap_err();
+
+        assert_eq!(err, ConfigError::BitsTooWide { total: 67 });
+    }
+
+    #[test]
+    fn test_builder_rejects_machine_id_out_of_range() {
+        let err = TimestampSeq::builder(16)
+            .machine_id_bits(4)
+            .build()
+            .unwrap_err();
+
+        assert_eq!(err, ConfigError::MachineIdOutOfRange { machine_id: 16, max: 15 });
+    }
+}
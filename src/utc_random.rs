@@ -0,0 +1,188 @@
+use std::sync::atomic::{AtomicU64, Ordering::Release, Ordering::SeqCst};
+
+use crate::timestamp_seq::{Clock, SystemClock};
+use crate::EPOCH;
+
+/// Bits reserved for the timestamp in the `u64` packing of [`UtcRandom`] ids;
+/// the remaining bits hold the random suffix.
+const TIMESTAMP_BITS: u8 = 42;
+const SUFFIX_BITS: u8 = 64 - TIMESTAMP_BITS;
+const SUFFIX_MASK: u64 = (1 << SUFFIX_BITS) - 1;
+
+/// Reads a fresh `u64` from the OS CSPRNG.
+///
+/// This crate has no external dependencies, so rather than pull one in just
+/// for this, Unix-likes talk to the kernel's RNG device directly (the same
+/// source `getrandom`-style crates ultimately read from). On other platforms
+/// there is no equivalent without a dependency, so this falls back to
+/// [`std::collections::hash_map::RandomState`], which is *not* a CSPRNG (see
+/// the caveat on [`UtcRandom`]).
+#[cfg(unix)]
+fn random_u64() -> u64 {
+    use std::fs::File;
+    use std::io::Read;
+
+    let mut buf = [0u8; 8];
+    File::open("/dev/urandom")
+        .and_then(|mut f| f.read_exact(&mut buf))
+        .expect("failed to read randomness from /dev/urandom");
+    u64::from_ne_bytes(buf)
+}
+
+#[cfg(not(unix))]
+fn random_u64() -> u64 {
+    use std::collections::hash_map::RandomState;
+    use std::hash::{BuildHasher, Hasher};
+
+    RandomState::new().build_hasher().finish()
+}
+
+/// CouchDB's `utc_random` algorithm: a millisecond timestamp prefix followed
+/// by a random suffix that increments by one for further ids generated in the
+/// same millisecond, and is reseeded from fresh randomness whenever the
+/// millisecond advances.
+///
+/// Unlike [`TimestampSeq`](crate::TimestampSeq), the suffix doesn't count up
+/// from a predictable, dense sequence that's trivial to enumerate from a
+/// handful of observed ids. How unpredictable it actually is depends on the
+/// reseed source: on Unix-likes that's the OS CSPRNG (`/dev/urandom`), which
+/// is a genuine unpredictability guarantee; on other platforms it currently
+/// falls back to `RandomState`, which is *not* a CSPRNG and should not be
+/// relied on where unguessability matters.
+pub struct UtcRandom<C: Clock = SystemClock> {
+    clock: C,
+    last_timestamp: AtomicU64,
+    suffix: AtomicU64,
+}
+
+impl UtcRandom<SystemClock> {
+    /// Creates a new `UtcRandom` generator using the system clock.
+    pub fn new() -> UtcRandom<SystemClock> {
+        UtcRandom::with_clock(SystemClock)
+    }
+}
+
+impl Default for UtcRandom<SystemClock> {
+    fn default() -> Self {
+        UtcRandom::new()
+    }
+}
+
+impl<C: Clock> UtcRandom<C> {
+    /// Creates a new `UtcRandom` generator using a custom [`Clock`] source.
+    pub fn with_clock(clock: C) -> UtcRandom<C> {
+        UtcRandom {
+            clock,
+            last_timestamp: AtomicU64::new(0),
+            suffix: AtomicU64::new(random_u64()),
+        }
+    }
+
+    fn next_parts(&mut self) -> (u64, u64) {
+        let now = self.clock.now_millis();
+        let last = self.last_timestamp.swap(now, SeqCst);
+        if now != last {
+            self.suffix.store(random_u64(), Release);
+        }
+        let suffix = self.suffix.fetch_add(1, SeqCst);
+        (now, suffix)
+    }
+
+    /// Generates the next id, packed into a `u64`: `TIMESTAMP_BITS` bits of
+    /// timestamp followed by a `SUFFIX_BITS`-bit random suffix.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use idable::UtcRandom;
+    ///
+    /// let mut gen = UtcRandom::new();
+    /// let id = gen.next_id();
+    /// println!("Generated id: {id}");
+    /// ```
+    pub fn next_id(&mut self) -> u64 {
+        let (timestamp, suffix) = self.next_parts();
+        (timestamp - EPOCH) << SUFFIX_BITS | (suffix & SUFFIX_MASK)
+    }
+
+    /// Generates the next id, packed into a `u128`: the full timestamp, in
+    /// milliseconds since [`EPOCH`], in the upper 64 bits, and the full 64-bit random suffix in
+    /// the lower 64 bits. Gives far more random bits than [`next_id`](Self::next_id)
+    /// at the cost of a wider output type.
+    pub fn next_id_u128(&mut self) -> u128 {
+        let (timestamp, suffix) = self.next_parts();
+        ((timestamp - EPOCH) as u128) << 64 | suffix as u128
+    }
+
+    /// Splits a `u64` id produced by [`next_id`](Self::next_id) back into its
+    /// `(timestamp, suffix)` parts.
+    pub fn into_parts(&self, id: u64) -> (u64, u64) {
+        (id >> SUFFIX_BITS, id & SUFFIX_MASK)
+    }
+
+    /// Splits a `u128` id produced by [`next_id_u128`](Self::next_id_u128)
+    /// back into its `(timestamp, suffix)` parts.
+    pub fn into_parts_u128(&self, id: u128) -> (u64, u64) {
+        ((id >> 64) as u64, id as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::MockClock;
+    use crate::{decode, encode};
+
+    #[test]
+    fn test_suffix_increments_within_same_millisecond() {
+        let mut gen = UtcRandom::with_clock(MockClock::new([EPOCH, EPOCH, EPOCH]));
+
+        let id1 = gen.next_id();
+        let id2 = gen.next_id();
+        let id3 = gen.next_id();
+
+        let (ts1, s1) = gen.into_parts(id1);
+        let (ts2, s2) = gen.into_parts(id2);
+        let (ts3, s3) = gen.into_parts(id3);
+
+        assert_eq!((ts1, ts2, ts3), (0, 0, 0));
+        assert_eq!(s2, s1.wrapping_add(1) & SUFFIX_MASK);
+        assert_eq!(s3, s2.wrapping_add(1) & SUFFIX_MASK);
+    }
+
+    #[test]
+    fn test_timestamp_advances_with_clock() {
+        let mut gen = UtcRandom::with_clock(MockClock::new([EPOCH, EPOCH + 1]));
+
+        let id1 = gen.next_id();
+        let id2 = gen.next_id();
+
+        let (ts1, _) = gen.into_parts(id1);
+        let (ts2, _) = gen.into_parts(id2);
+
+        assert_eq!(ts1, 0);
+        assert_eq!(ts2, 1);
+    }
+
+    #[test]
+    fn test_u128_packing_roundtrips() {
+        let mut gen = UtcRandom::with_clock(MockClock::new([EPOCH, EPOCH]));
+
+        let id1 = gen.next_id_u128();
+        let id2 = gen.next_id_u128();
+
+        let (ts1, s1) = gen.into_parts_u128(id1);
+        let (ts2, s2) = gen.into_parts_u128(id2);
+
+        assert_eq!((ts1, ts2), (0, 0));
+        assert_eq!(s2, s1.wrapping_add(1));
+    }
+
+    #[test]
+    fn test_u64_id_roundtrips_through_base32_encoding() {
+        let mut gen = UtcRandom::with_clock(MockClock::new([EPOCH]));
+        let id = gen.next_id();
+
+        assert_eq!(decode(&encode(id)).unwrap(), id);
+    }
+}